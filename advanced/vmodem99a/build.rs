@@ -0,0 +1,44 @@
+use std::env;
+use std::process::Command;
+
+// Capture build metadata for `--version` and the `about` command
+fn main() {
+    let commit = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let rustc_version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|version| !version.is_empty())
+        .unwrap_or_else(|| "unknown".to_string());
+    
+    let mut features: Vec<String> = env::vars()
+        .filter_map(|(key, _)| key.strip_prefix("CARGO_FEATURE_").map(|f| f.to_lowercase().replace('_', "-")))
+        .collect();
+    features.sort();
+    let features = if features.is_empty() { "none".to_string() } else { features.join(", ") };
+    
+    let build_date = chrono::Utc::now().format("%Y-%m-%d %H:%M UTC");
+    
+    println!("cargo:rustc-env=VMODEM_GIT_COMMIT={}", commit);
+    println!("cargo:rustc-env=VMODEM_BUILD_DATE={}", build_date);
+    println!("cargo:rustc-env=VMODEM_RUSTC_VERSION={}", rustc_version);
+    println!("cargo:rustc-env=VMODEM_FEATURES={}", features);
+    
+    // Refresh the commit hash when HEAD moves
+    if let Ok(output) = Command::new("git").args(["rev-parse", "--git-dir"]).output() {
+        let git_dir = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if output.status.success() && !git_dir.is_empty() {
+            println!("cargo:rerun-if-changed={}/HEAD", git_dir);
+        }
+    }
+    println!("cargo:rerun-if-changed=build.rs");
+}
@@ -0,0 +1,271 @@
+use std::io::{Read, Write};
+use std::net::{TcpListener, UdpSocket};
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+use std::thread;
+
+// A fresh home for one test, so runs never read or write the user's files
+fn scratch_home(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("vmodem99a-cli-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+// Run the binary non-interactively with its config kept under `home`
+fn vmodem(home: &PathBuf, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_vmodem99a"))
+        .args(["--no-wizard", "--config"])
+        .arg(home.join("config"))
+        .args(args)
+        .env("HOME", home)
+        .env("NO_COLOR", "1")
+        .stdin(Stdio::null())
+        .output()
+        .unwrap()
+}
+
+// Answer every request with the same body, returning the base URL
+fn serve(content_type: &'static str, body: &'static str) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let mut request = [0u8; 4096];
+            let _ = stream.read(&mut request);
+            let _ = write!(stream, "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                content_type, body.len(), body);
+        }
+    });
+    url
+}
+
+#[test]
+fn quiet_mode_prints_no_banner() {
+    let home = scratch_home("quiet");
+    
+    let loud = vmodem(&home, &["history", "search", "nothing"]);
+    assert!(String::from_utf8_lossy(&loud.stderr).contains("Virtual Modem Terminal"));
+    
+    for flag in ["--quiet", "-q"] {
+        let quiet = vmodem(&home, &[flag, "history", "search", "nothing"]);
+        assert!(quiet.status.success());
+        let stderr = String::from_utf8_lossy(&quiet.stderr);
+        assert!(!stderr.contains("Virtual Modem Terminal"), "{}", stderr);
+        assert!(!stderr.contains("[STATUS]"), "{}", stderr);
+        assert!(!String::from_utf8_lossy(&quiet.stdout).contains("Virtual Modem Terminal"));
+    }
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+#[test]
+fn http_body_goes_to_stdout_and_chatter_to_stderr() {
+    let home = scratch_home("streams");
+    let url = serve("text/plain", "hello-body");
+    
+    let output = vmodem(&home, &["http", &url]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stdout.contains("hello-body"), "{}", stdout);
+    assert!(stdout.contains("HTTP 200"), "{}", stdout);
+    for chatter in ["Virtual Modem Terminal", "[STATUS]", "Connecting via HTTP"] {
+        assert!(stderr.contains(chatter), "{}", stderr);
+        assert!(!stdout.contains(chatter), "{}", stdout);
+    }
+    assert!(!stderr.contains("hello-body"), "{}", stderr);
+    
+    // wget's progress lines are chatter too; only the result line reaches stdout
+    if Command::new("wget").arg("--version").output().is_ok() {
+        let target = home.join("body.txt");
+        let output = vmodem(&home, &["download", &url, target.to_str().unwrap()]);
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert_eq!(stdout.lines().count(), 1, "{}", stdout);
+        assert!(stderr.contains("saved"), "{}", stderr);
+        assert!(!stdout.contains("saved") && !stdout.contains("[STATUS]"), "{}", stdout);
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "hello-body");
+    }
+    
+    // Errors stay on stderr even in quiet mode
+    let output = vmodem(&home, &["-q", "http", "http://127.0.0.1:9/"]);
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty(), "{}", String::from_utf8_lossy(&output.stdout));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("[ERROR]"));
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+// Run a command with --json and parse the single line it prints
+fn json_result(home: &PathBuf, args: &[&str]) -> (bool, serde_json::Value) {
+    let output = vmodem(home, &[&["--json"], args].concat());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.lines().count(), 1, "{}", stdout);
+    (output.status.success(), serde_json::from_str(&stdout).unwrap())
+}
+
+#[test]
+fn json_mode_prints_valid_json_for_each_command() {
+    let home = scratch_home("json");
+    let url = serve("text/html", "<p>hello</p>");
+    
+    let (ok, http) = json_result(&home, &["http", &url]);
+    assert!(ok);
+    assert_eq!(http["status"], 200);
+    assert_eq!(http["body_bytes"], 12);
+    assert_eq!(http["headers"]["content-type"], "text/html");
+    assert!(http["elapsed_ms"].is_u64());
+    
+    let (ok, mirror) = json_result(&home, &["download", "--mirror", &url, "--output-dir", home.to_str().unwrap()]);
+    assert!(ok);
+    assert_eq!(mirror["files"], 1);
+    assert_eq!(mirror["failed"], 0);
+    
+    if Command::new("wget").arg("--version").output().is_ok() {
+        let target = home.join("page.html");
+        let (ok, download) = json_result(&home, &["download", &url, target.to_str().unwrap()]);
+        assert!(ok);
+        assert_eq!(download["filename"], target.to_str().unwrap());
+        assert_eq!(download["bytes"], 12);
+    }
+    
+    // A one-shot SNTP server answering with 2024-01-01T00:00:00Z
+    let ntp = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let server = ntp.local_addr().unwrap().to_string();
+    thread::spawn(move || {
+        let mut request = [0u8; 48];
+        let (_, peer) = ntp.recv_from(&mut request).unwrap();
+        let mut reply = [0u8; 48];
+        reply[0] = 0x1C;
+        reply[40..44].copy_from_slice(&(1_704_067_200u32 + 2_208_988_800).to_be_bytes());
+        ntp.send_to(&reply, peer).unwrap();
+    });
+    let (ok, time) = json_result(&home, &["time", &server]);
+    assert!(ok);
+    assert_eq!(time["server"], server);
+    assert_eq!(time["utc"], "2024-01-01T00:00:00Z");
+    assert!(time["offset_ms"].is_i64());
+    
+    let (ok, error) = json_result(&home, &["http", "http://127.0.0.1:9/"]);
+    assert!(!ok);
+    assert!(error["error"].is_string());
+    assert!(error["message"].is_string());
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+// A vmodem-<name> script on PATH runs for an unknown command, even one starting with "at"
+#[cfg(unix)]
+#[test]
+fn plugins_run_after_built_ins_and_at_commands() {
+    use std::os::unix::fs::PermissionsExt;
+    let home = scratch_home("plugin");
+    let bin = home.join("bin");
+    std::fs::create_dir_all(&bin).unwrap();
+    for name in ["attach", "help"] {
+        let script = bin.join(format!("vmodem-{}", name));
+        std::fs::write(&script, format!("#!/bin/sh\necho \"plugin {} $* baud=$VMODEM_BAUD_RATE\"\n", name)).unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+    let path = std::env::join_paths(std::iter::once(bin).chain(std::env::split_paths(&std::env::var_os("PATH").unwrap()))).unwrap();
+    let run = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_vmodem99a"))
+            .args(["--no-wizard", "-q", "--config"])
+            .arg(home.join("config"))
+            .args(args)
+            .env("HOME", &home)
+            .env("PATH", &path)
+            .env("NO_COLOR", "1")
+            .stdin(Stdio::null())
+            .output()
+            .unwrap()
+    };
+    
+    let attach = run(&["attach", "disk", "1"]);
+    assert!(attach.status.success());
+    assert_eq!(String::from_utf8_lossy(&attach.stdout), "plugin attach disk 1 baud=1200\n");
+    
+    // Built-ins and AT commands win over a plugin of the same name
+    let help = run(&["help"]);
+    assert!(!String::from_utf8_lossy(&help.stdout).contains("plugin help"));
+    let at = run(&["ATI0"]);
+    assert!(String::from_utf8_lossy(&at.stdout).contains("VModem Model 99/A"));
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+// Without a terminal or --yes, an overwrite is refused and the command fails as cancelled
+#[test]
+fn refused_overwrites_exit_as_cancelled() {
+    let home = scratch_home("overwrite");
+    let url = serve("text/html", "<p>hello</p>");
+    let target = home.join("page.html");
+    std::fs::write(&target, "keep me").unwrap();
+    
+    let download = vmodem(&home, &["-q", "download", &url, target.to_str().unwrap()]);
+    assert_eq!(download.status.code(), Some(130));
+    assert_eq!(std::fs::read_to_string(&target).unwrap(), "keep me");
+    
+    let host = url.trim_start_matches("http://").trim_end_matches('/').split(':').next().unwrap();
+    std::fs::create_dir_all(home.join(host)).unwrap();
+    let mirror = vmodem(&home, &["-q", "download", "--mirror", &url, "--output-dir", home.to_str().unwrap()]);
+    assert_eq!(mirror.status.code(), Some(130));
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+// Several URLs only ever GET each one, so body and method options are refused up front
+#[test]
+fn http_with_several_urls_rejects_single_url_options() {
+    let home = scratch_home("multi");
+    let url = serve("text/plain", "ok");
+    let second = format!("{}second", url);
+    
+    let ok = vmodem(&home, &["-q", "http", &url, &second, "--auth", "sysop:secret", "--retries", "1"]);
+    assert!(ok.status.success(), "{}", String::from_utf8_lossy(&ok.stderr));
+    assert_eq!(String::from_utf8_lossy(&ok.stdout).matches("HTTP 200").count(), 2);
+    
+    for args in [
+        vec!["POST"],
+        vec!["--form", "a=b"],
+        vec!["-o", "out.txt"],
+        vec!["--hash", "sha256"],
+    ] {
+        let output = vmodem(&home, &[&["-q", "http", &url, &second], &args[..]].concat());
+        assert_eq!(output.status.code(), Some(64), "{:?}", args);
+        assert!(String::from_utf8_lossy(&output.stderr).contains("[ERROR]"), "{:?}", args);
+    }
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+// `config edit` hands the editor a temp copy only the owner can read
+#[cfg(unix)]
+#[test]
+fn config_edit_uses_a_private_temp_file() {
+    use std::os::unix::fs::PermissionsExt;
+    let home = scratch_home("config-edit");
+    let editor = home.join("editor.sh");
+    std::fs::write(&editor, format!("#!/bin/sh\nstat -c %a \"$1\" > {}\n", home.join("mode").display())).unwrap();
+    std::fs::set_permissions(&editor, std::fs::Permissions::from_mode(0o755)).unwrap();
+    
+    let output = Command::new(env!("CARGO_BIN_EXE_vmodem99a"))
+        .args(["--no-wizard", "-q", "--config"])
+        .arg(home.join("config"))
+        .args(["config", "edit"])
+        .env("HOME", &home)
+        .env("EDITOR", &editor)
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(std::fs::read_to_string(home.join("mode")).unwrap().trim(), "600");
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+// A session started from inside another is refused at once instead of on every tick
+#[test]
+fn nested_sessions_find_the_line_busy() {
+    let home = scratch_home("busy");
+    let output = vmodem(&home, &["-q", "every", "1", "watch", "http://127.0.0.1:9/", "1"]);
+    assert_eq!(output.status.code(), Some(75));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Line busy: 'every 1 watch"));
+    let _ = std::fs::remove_dir_all(&home);
+}